@@ -135,6 +135,45 @@ fn hash_files(file_paths: Vec<String>) -> PyResult<HashMap<String, String>> {
     Ok(hashes)
 }
 
+/// Files at or above this size are hashed with BLAKE3's tree structure
+/// spread across the rayon pool instead of a single-threaded pass.
+const BLAKE3_PARALLEL_THRESHOLD: u64 = 1 << 20; // 1 MiB
+
+/// Parallel BLAKE3 file hash computation (for cache invalidation).
+///
+/// Like [`hash_files`] but produces 256-bit BLAKE3 digests. Files at or above
+/// `large_file_threshold` bytes (default 1 MiB) are hashed with
+/// `Hasher::update_rayon`, which walks BLAKE3's tree of 1024-byte chunks and
+/// hashes its subtrees across the rayon pool before combining the chaining
+/// values; the subtree sizes are chosen by the blake3 crate and are not fixed
+/// at 1 MiB. Smaller files take the serial path.
+#[pyfunction]
+#[pyo3(signature = (file_paths, large_file_threshold=None))]
+fn hash_files_blake3(
+    file_paths: Vec<String>,
+    large_file_threshold: Option<u64>,
+) -> PyResult<HashMap<String, String>> {
+    let threshold = large_file_threshold.unwrap_or(BLAKE3_PARALLEL_THRESHOLD);
+
+    let hashes: HashMap<String, String> = file_paths
+        .par_iter()
+        .filter_map(|path| {
+            let content = fs::read(path).ok()?;
+            let mut hasher = blake3::Hasher::new();
+            if content.len() as u64 >= threshold {
+                // Tree-parallel hashing of a single large file over rayon.
+                hasher.update_rayon(&content);
+            } else {
+                hasher.update(&content);
+            }
+            let hash = hasher.finalize().to_hex().to_string();
+            Some((path.clone(), hash))
+        })
+        .collect();
+
+    Ok(hashes)
+}
+
 /// Fast complexity analyzer (simplified Radon)
 #[pyfunction]
 fn analyze_complexity(source: String) -> PyResult<u32> {
@@ -259,6 +298,7 @@ fn generate_cache_key(file_path: String, config_hash: String) -> PyResult<String
 fn quality_worker_rust(_py: Python, m: &PyModule) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(scan_python_files, m)?)?;
     m.add_function(wrap_pyfunction!(hash_files, m)?)?;
+    m.add_function(wrap_pyfunction!(hash_files_blake3, m)?)?;
     m.add_function(wrap_pyfunction!(analyze_complexity, m)?)?;
     m.add_function(wrap_pyfunction!(analyze_files_batch, m)?)?;
     m.add_function(wrap_pyfunction!(generate_cache_key, m)?)?;
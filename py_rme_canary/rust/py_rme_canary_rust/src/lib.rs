@@ -1,4 +1,6 @@
 use pyo3::prelude::*;
+use rayon::prelude::*;
+use std::collections::HashMap;
 
 // ---------------------------------------------------------------------------
 // 1. Spawn entry names (existing)
@@ -13,36 +15,171 @@ struct SpawnAreaPayload {
     entries: Vec<(String, i64, i64)>,
 }
 
-fn compute_spawn_entry_names(payload: &[SpawnAreaPayload], x: i64, y: i64, z: i64) -> Vec<String> {
-    let mut names: Vec<String> = Vec::new();
+/// Collect the entry names an `area` exposes at the offset `(dx, dy)` from its
+/// center. Empty when the offset lies outside the area's radius.
+fn entries_at_offset(area: &SpawnAreaPayload, dx: i64, dy: i64) -> Vec<String> {
+    let radius = area.radius.max(0);
+    if dx.abs().max(dy.abs()) > radius {
+        return Vec::new();
+    }
+    area.entries
+        .iter()
+        .filter(|(_, edx, edy)| *edx == dx && *edy == dy)
+        .map(|(name, _, _)| name.clone())
+        .collect()
+}
+
+/// A node of a per-floor 2D k-d tree over spawn-area centers.
+struct KdNode {
+    area: usize,
+    axis: u8, // 0 = x, 1 = y
+    left: Option<usize>,
+    right: Option<usize>,
+}
+
+/// k-d tree for a single floor, plus the largest radius on that floor so a
+/// descent can prune by Chebyshev distance.
+struct KdFloor {
+    nodes: Vec<KdNode>,
+    root: Option<usize>,
+    max_radius: i64,
+}
 
-    for area in payload {
-        if area.z != z {
-            continue;
+impl KdFloor {
+    fn build(areas: &[SpawnAreaPayload], indices: &[usize]) -> Self {
+        let mut nodes: Vec<KdNode> = Vec::with_capacity(indices.len());
+        let mut idx: Vec<usize> = indices.to_vec();
+        let max_radius = indices
+            .iter()
+            .map(|&i| areas[i].radius.max(0))
+            .max()
+            .unwrap_or(0);
+        let root = Self::build_range(areas, &mut idx, 0, &mut nodes);
+        Self {
+            nodes,
+            root,
+            max_radius,
         }
-        let dx = x - area.x;
-        let dy = y - area.y;
-        let radius = area.radius.max(0);
-        if dx.abs().max(dy.abs()) > radius {
-            continue;
+    }
+
+    fn build_range(
+        areas: &[SpawnAreaPayload],
+        idx: &mut [usize],
+        depth: u8,
+        nodes: &mut Vec<KdNode>,
+    ) -> Option<usize> {
+        if idx.is_empty() {
+            return None;
         }
+        let axis = depth % 2;
+        idx.sort_by_key(|&i| if axis == 0 { areas[i].x } else { areas[i].y });
+        let mid = idx.len() / 2;
+        let area = idx[mid];
+        let (left_slice, right_slice) = idx.split_at_mut(mid);
+        let left = Self::build_range(areas, left_slice, depth + 1, nodes);
+        let right = Self::build_range(areas, &mut right_slice[1..], depth + 1, nodes);
+        let node = KdNode {
+            area,
+            axis,
+            left,
+            right,
+        };
+        nodes.push(node);
+        Some(nodes.len() - 1)
+    }
 
-        for (name, entry_dx, entry_dy) in &area.entries {
-            if *entry_dx == dx && *entry_dy == dy {
-                names.push(name.clone());
-            }
+    /// Visit every area whose center is within the floor's max radius of
+    /// `(x, y)` along both axes, pushing the original index of each.
+    fn query(&self, areas: &[SpawnAreaPayload], x: i64, y: i64, out: &mut Vec<usize>) {
+        self.query_node(areas, self.root, x, y, out);
+    }
+
+    fn query_node(
+        &self,
+        areas: &[SpawnAreaPayload],
+        node: Option<usize>,
+        x: i64,
+        y: i64,
+        out: &mut Vec<usize>,
+    ) {
+        let Some(n) = node else { return };
+        let node = &self.nodes[n];
+        let area = &areas[node.area];
+        if (x - area.x).abs().max((y - area.y).abs()) <= self.max_radius {
+            out.push(node.area);
         }
-        if !names.is_empty() {
-            break;
+        let (coord, split) = if node.axis == 0 {
+            (x, area.x)
+        } else {
+            (y, area.y)
+        };
+        // Only descend a side if it can still hold a center within max_radius.
+        if coord - self.max_radius <= split {
+            self.query_node(areas, node.left, x, y, out);
+        }
+        if coord + self.max_radius >= split {
+            self.query_node(areas, node.right, x, y, out);
         }
     }
+}
 
-    names
+/// Persistent spatial index over spawn areas, built once per map load.
+///
+/// Answers [`entry_names_at`](SpawnIndex::entry_names_at) in roughly
+/// `O(log N + k)` by descending a per-floor k-d tree and pruning subtrees that
+/// cannot contain the query point, instead of scanning every area.
+#[pyclass]
+struct SpawnIndex {
+    areas: Vec<SpawnAreaPayload>,
+    floors: HashMap<i64, KdFloor>,
+}
+
+#[pymethods]
+impl SpawnIndex {
+    #[new]
+    fn new(payload: Vec<SpawnAreaPayload>) -> Self {
+        let mut by_floor: HashMap<i64, Vec<usize>> = HashMap::new();
+        for (i, area) in payload.iter().enumerate() {
+            by_floor.entry(area.z).or_default().push(i);
+        }
+        let floors = by_floor
+            .into_iter()
+            .map(|(z, indices)| (z, KdFloor::build(&payload, &indices)))
+            .collect();
+        Self {
+            areas: payload,
+            floors,
+        }
+    }
+
+    /// Entry names exposed at `(x, y, z)`.
+    ///
+    /// Returns the entries of the first area (in insertion order) that both
+    /// contains the point and has a matching entry offset;
+    /// [`spawn_entry_names_at_cursor`] gives the same result through a
+    /// throwaway index.
+    fn entry_names_at(&self, x: i64, y: i64, z: i64) -> Vec<String> {
+        let Some(floor) = self.floors.get(&z) else {
+            return Vec::new();
+        };
+        let mut candidates = Vec::new();
+        floor.query(&self.areas, x, y, &mut candidates);
+        candidates.sort_unstable();
+        for area_idx in candidates {
+            let area = &self.areas[area_idx];
+            let names = entries_at_offset(area, x - area.x, y - area.y);
+            if !names.is_empty() {
+                return names;
+            }
+        }
+        Vec::new()
+    }
 }
 
 #[pyfunction]
 fn spawn_entry_names_at_cursor(payload: Vec<SpawnAreaPayload>, x: i64, y: i64, z: i64) -> Vec<String> {
-    compute_spawn_entry_names(&payload, x, y, z)
+    // Thin wrapper: build a throwaway index for a single query.
+    SpawnIndex::new(payload).entry_names_at(x, y, z)
 }
 
 // ---------------------------------------------------------------------------
@@ -81,6 +218,247 @@ fn sprite_hash(pixel_data: &[u8], width: u32, height: u32) -> u64 {
     compute_fnv1a_64(&buf)
 }
 
+// ---------------------------------------------------------------------------
+// 2b. BLAKE3 content hashing  (NEW – cryptographic 256-bit digests)
+// ---------------------------------------------------------------------------
+
+fn compute_blake3(data: &[u8]) -> [u8; 32] {
+    *blake3::hash(data).as_bytes()
+}
+
+/// BLAKE3 256-bit hash of raw bytes.
+///
+/// Unlike [`fnv1a_64_hash`] this is cryptographically strong; prefer it for
+/// cache keys where collision resistance matters.
+#[pyfunction]
+fn blake3_hash(data: &[u8]) -> [u8; 32] {
+    compute_blake3(data)
+}
+
+/// BLAKE3 hash of sprite pixel data including dimensions.
+///
+/// Folds width/height as LE `u32` prefixes into the hasher before the pixel
+/// bytes, matching the layout of [`sprite_hash`] but with a 256-bit digest.
+#[pyfunction]
+fn sprite_hash_256(pixel_data: &[u8], width: u32, height: u32) -> [u8; 32] {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(&width.to_le_bytes());
+    hasher.update(&height.to_le_bytes());
+    hasher.update(pixel_data);
+    *hasher.finalize().as_bytes()
+}
+
+// ---------------------------------------------------------------------------
+// 2c. Content-defined chunking + deduplication  (NEW)
+// ---------------------------------------------------------------------------
+
+/// Gear table for the rolling hash. Filled deterministically with a SplitMix64
+/// stream so the chunk boundaries are stable across builds and platforms.
+const fn build_gear_table() -> [u64; 256] {
+    let mut table = [0u64; 256];
+    let mut state: u64 = 0x2545_F491_4F6C_DD1D;
+    let mut i = 0;
+    while i < 256 {
+        state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^= z >> 31;
+        table[i] = z;
+        i += 1;
+    }
+    table
+}
+
+const GEAR: [u64; 256] = build_gear_table();
+
+/// Split `data` into content-defined chunks using a Gear-based rolling hash.
+///
+/// A boundary is declared when `(h & mask) == 0`, where `mask` derives from
+/// `avg` (the target average chunk size); `min`/`max` bound the chunk length.
+/// Each chunk is identified by its BLAKE3 digest.
+///
+/// Returns `(offset, len, chunk_hash)` triples covering the whole input.
+#[pyfunction]
+fn chunk_and_hash(data: &[u8], avg: u32, min: u32, max: u32) -> Vec<(u64, u32, [u8; 32])> {
+    let min = min.max(1) as usize;
+    let max = max.max(min as u32) as usize;
+    let log2_avg = 31 - avg.max(2).leading_zeros();
+    let mask: u64 = (1u64 << log2_avg) - 1;
+
+    let mut chunks = Vec::new();
+    let mut start = 0usize;
+    let mut hash: u64 = 0;
+
+    for (i, &byte) in data.iter().enumerate() {
+        hash = (hash << 1).wrapping_add(GEAR[byte as usize]);
+        let len = i - start + 1;
+        let at_boundary = len >= min && (hash & mask) == 0;
+        if at_boundary || len >= max {
+            chunks.push((
+                start as u64,
+                len as u32,
+                compute_blake3(&data[start..=i]),
+            ));
+            start = i + 1;
+            hash = 0;
+        }
+    }
+
+    if start < data.len() {
+        chunks.push((
+            start as u64,
+            (data.len() - start) as u32,
+            compute_blake3(&data[start..]),
+        ));
+    }
+
+    chunks
+}
+
+/// Deduplication statistics for a chunked asset stream.
+#[pyclass]
+#[derive(Debug, Clone)]
+struct DedupStats {
+    #[pyo3(get)]
+    total_bytes: u64,
+    #[pyo3(get)]
+    unique_bytes: u64,
+}
+
+#[pymethods]
+impl DedupStats {
+    /// Fraction of bytes eliminated by deduplication, in `[0.0, 1.0]`.
+    #[getter]
+    fn dedup_ratio(&self) -> f64 {
+        if self.total_bytes == 0 {
+            0.0
+        } else {
+            1.0 - (self.unique_bytes as f64 / self.total_bytes as f64)
+        }
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "DedupStats(total_bytes={}, unique_bytes={}, dedup_ratio={:.4})",
+            self.total_bytes,
+            self.unique_bytes,
+            self.dedup_ratio()
+        )
+    }
+}
+
+/// Chunk `data` and report how many bytes survive deduplication by chunk hash.
+#[pyfunction]
+fn dedup_stats(data: &[u8], avg: u32, min: u32, max: u32) -> DedupStats {
+    use std::collections::HashSet;
+
+    let chunks = chunk_and_hash(data, avg, min, max);
+    let mut seen: HashSet<[u8; 32]> = HashSet::new();
+    let mut total_bytes = 0u64;
+    let mut unique_bytes = 0u64;
+
+    for (_, len, hash) in &chunks {
+        total_bytes += *len as u64;
+        if seen.insert(*hash) {
+            unique_bytes += *len as u64;
+        }
+    }
+
+    DedupStats {
+        total_bytes,
+        unique_bytes,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// 2d. MinHash sprite similarity  (NEW – approximate cross-version matching)
+// ---------------------------------------------------------------------------
+
+/// Bottom-k MinHash sketch of a sprite.
+///
+/// Shingles the dimension-prefixed pixel buffer into overlapping 4-byte
+/// windows, hashes each with FNV-1a, and keeps the `k` smallest distinct hash
+/// values (returned ascending). This is the cross-version counterpart to
+/// [`sprite_hash`]: two sprites differing by a handful of pixels share most of
+/// their sketch, whereas an exact hash would not match at all.
+#[pyfunction]
+fn sprite_minhash(pixel_data: &[u8], width: u32, height: u32, k: usize) -> Vec<u64> {
+    use std::collections::BTreeSet;
+
+    let mut buf = Vec::with_capacity(8 + pixel_data.len());
+    buf.extend_from_slice(&width.to_le_bytes());
+    buf.extend_from_slice(&height.to_le_bytes());
+    buf.extend_from_slice(pixel_data);
+
+    // Bottom-k: the BTreeSet keeps values sorted and distinct; trim the tail
+    // once it grows past k so memory stays bounded for large sprites.
+    let mut bottom: BTreeSet<u64> = BTreeSet::new();
+    for window in buf.windows(4) {
+        let h = compute_fnv1a_64(window);
+        if bottom.len() < k {
+            bottom.insert(h);
+        } else if let Some(&max) = bottom.iter().next_back() {
+            if h < max && bottom.insert(h) {
+                bottom.pop_last();
+            }
+        }
+    }
+
+    bottom.into_iter().collect()
+}
+
+/// Estimate the Jaccard similarity of two bottom-k sketches.
+///
+/// Merges both sketches, takes the smallest `k = min(|a|, |b|)` distinct
+/// values, and reports the fraction of those present in both inputs.
+#[pyfunction]
+fn minhash_jaccard(a: &[u64], b: &[u64]) -> f64 {
+    use std::collections::BTreeSet;
+
+    let k = a.len().min(b.len());
+    if k == 0 {
+        return 0.0;
+    }
+    let set_a: BTreeSet<u64> = a.iter().copied().collect();
+    let set_b: BTreeSet<u64> = b.iter().copied().collect();
+
+    let mut merged: BTreeSet<u64> = set_a.iter().copied().collect();
+    merged.extend(set_b.iter().copied());
+
+    let mut shared = 0usize;
+    for value in merged.into_iter().take(k) {
+        if set_a.contains(&value) && set_b.contains(&value) {
+            shared += 1;
+        }
+    }
+    shared as f64 / k as f64
+}
+
+/// Match old sprite sketches against new ones above a Jaccard `threshold`.
+///
+/// Runs over the rayon pool and returns candidate `(old_index, new_index)`
+/// pairs, letting the cross-version mapper auto-suggest sprite remappings
+/// instead of relying on exact-hash equality.
+#[pyfunction]
+fn match_sprites(
+    old_sketches: Vec<Vec<u64>>,
+    new_sketches: Vec<Vec<u64>>,
+    threshold: f64,
+) -> Vec<(usize, usize)> {
+    old_sketches
+        .par_iter()
+        .enumerate()
+        .flat_map_iter(|(oi, old)| {
+            new_sketches
+                .iter()
+                .enumerate()
+                .filter(move |(_, new)| minhash_jaccard(old, new) >= threshold)
+                .map(move |(ni, _)| (oi, ni))
+        })
+        .collect()
+}
+
 // ---------------------------------------------------------------------------
 // 3. Minimap pixel buffer rendering  (NEW – ~50-100× speedup)
 // ---------------------------------------------------------------------------
@@ -146,35 +524,113 @@ fn render_minimap_buffer(
 // 4. PNG IDAT assembly  (NEW – ~10-30× speedup for large images)
 // ---------------------------------------------------------------------------
 
-/// Assemble raw PNG IDAT data: prepend filter byte (0x00) to each row,
-/// then zlib-compress the result.
+/// Paeth predictor: picks whichever of `a` (left), `b` (above), `c`
+/// (upper-left) is closest to `p = a + b - c`, ties favouring a then b.
+fn paeth_predictor(a: u8, b: u8, c: u8) -> u8 {
+    let (a, b, c) = (a as i32, b as i32, c as i32);
+    let p = a + b - c;
+    let pa = (p - a).abs();
+    let pb = (p - b).abs();
+    let pc = (p - c).abs();
+    if pa <= pb && pa <= pc {
+        a as u8
+    } else if pb <= pc {
+        b as u8
+    } else {
+        c as u8
+    }
+}
+
+/// Sum of the filtered bytes reinterpreted as signed `i8` absolute values;
+/// lower scores compress better.
+fn filter_score(row: &[u8]) -> u64 {
+    row.iter().map(|&b| (b as i8).unsigned_abs() as u64).sum()
+}
+
+/// Compute one filtered scanline for the given PNG filter type (0–4).
+///
+/// `bpp` is the bytes-per-pixel stride; out-of-bounds neighbours are 0.
+fn filter_scanline(ftype: u8, raw: &[u8], prior: &[u8], bpp: usize, out: &mut Vec<u8>) {
+    out.clear();
+    for x in 0..raw.len() {
+        let a = if x >= bpp { raw[x - bpp] } else { 0 };
+        let b = prior.get(x).copied().unwrap_or(0);
+        let c = if x >= bpp {
+            prior.get(x - bpp).copied().unwrap_or(0)
+        } else {
+            0
+        };
+        let v = match ftype {
+            0 => raw[x],
+            1 => raw[x].wrapping_sub(a),
+            2 => raw[x].wrapping_sub(b),
+            3 => raw[x].wrapping_sub(((a as u16 + b as u16) / 2) as u8),
+            4 => raw[x].wrapping_sub(paeth_predictor(a, b, c)),
+            _ => raw[x],
+        };
+        out.push(v);
+    }
+}
+
+/// Assemble raw PNG IDAT data: prepend a filter byte to each row, then
+/// zlib-compress the result.
+///
+/// `filter_strategy` selects how each scanline's filter byte is chosen:
+/// `"none"` (the default) emits filter 0 for every row, matching the legacy
+/// behaviour; `"adaptive"` tries all five PNG filters per row and keeps the
+/// one with the smallest minimum-sum-of-absolute-differences score.
 ///
 /// Returns compressed bytes ready to be wrapped in an IDAT chunk.
 #[pyfunction]
-fn assemble_png_idat(image_data: &[u8], width: u32, height: u32) -> Vec<u8> {
-    let row_bytes = (width as usize) * 3;
+#[pyo3(signature = (image_data, width, height, filter_strategy=None))]
+fn assemble_png_idat(
+    image_data: &[u8],
+    width: u32,
+    height: u32,
+    filter_strategy: Option<&str>,
+) -> Vec<u8> {
+    const BPP: usize = 3; // RGB
+    let row_bytes = (width as usize) * BPP;
     let h = height as usize;
+    let adaptive = matches!(filter_strategy, Some("adaptive"));
 
-    // Pre-allocate: each row gets +1 filter byte
+    // Materialise each unfiltered row (zero-padded if the input is short).
     let mut raw = Vec::with_capacity(h * (row_bytes + 1));
+    let mut prior = vec![0u8; row_bytes];
+    let mut row = vec![0u8; row_bytes];
+    let mut candidate = Vec::with_capacity(row_bytes);
+    let mut best = Vec::with_capacity(row_bytes);
+
     for y in 0..h {
-        raw.push(0u8); // Filter byte = None
         let start = y * row_bytes;
-        let end = start + row_bytes;
-        if end <= image_data.len() {
-            raw.extend_from_slice(&image_data[start..end]);
-        } else {
-            // Pad with zeros if data is short
-            let available = if start < image_data.len() {
-                image_data.len() - start
-            } else {
-                0
-            };
-            if available > 0 {
-                raw.extend_from_slice(&image_data[start..start + available]);
+        let available = image_data.len().saturating_sub(start).min(row_bytes);
+        row[..available].copy_from_slice(&image_data[start..start + available]);
+        for b in row[available..].iter_mut() {
+            *b = 0;
+        }
+
+        if adaptive {
+            let mut best_type = 0u8;
+            let mut best_score = u64::MAX;
+            best.clear();
+            for ftype in 0..=4u8 {
+                filter_scanline(ftype, &row, &prior, BPP, &mut candidate);
+                let score = filter_score(&candidate);
+                if score < best_score {
+                    best_score = score;
+                    best_type = ftype;
+                    best.clear();
+                    best.extend_from_slice(&candidate);
+                }
             }
-            raw.resize(raw.len() + row_bytes - available, 0);
+            raw.push(best_type);
+            raw.extend_from_slice(&best);
+        } else {
+            raw.push(0u8); // Filter byte = None
+            raw.extend_from_slice(&row);
         }
+
+        prior.copy_from_slice(&row);
     }
 
     // Use miniz_oxide (Rust's built-in zlib) for compression
@@ -188,8 +644,17 @@ fn assemble_png_idat(image_data: &[u8], width: u32, height: u32) -> Vec<u8> {
 #[pymodule]
 fn py_rme_canary_rust(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(spawn_entry_names_at_cursor, m)?)?;
+    m.add_class::<SpawnIndex>()?;
     m.add_function(wrap_pyfunction!(fnv1a_64_hash, m)?)?;
     m.add_function(wrap_pyfunction!(sprite_hash, m)?)?;
+    m.add_function(wrap_pyfunction!(blake3_hash, m)?)?;
+    m.add_function(wrap_pyfunction!(sprite_hash_256, m)?)?;
+    m.add_function(wrap_pyfunction!(chunk_and_hash, m)?)?;
+    m.add_function(wrap_pyfunction!(dedup_stats, m)?)?;
+    m.add_function(wrap_pyfunction!(sprite_minhash, m)?)?;
+    m.add_function(wrap_pyfunction!(minhash_jaccard, m)?)?;
+    m.add_function(wrap_pyfunction!(match_sprites, m)?)?;
+    m.add_class::<DedupStats>()?;
     m.add_function(wrap_pyfunction!(render_minimap_buffer, m)?)?;
     m.add_function(wrap_pyfunction!(assemble_png_idat, m)?)?;
     Ok(())
@@ -212,7 +677,7 @@ mod tests {
                 ("Warlock".to_string(), 0, 1),
             ],
         }];
-        let names = compute_spawn_entry_names(&payload, 100, 200, 7);
+        let names = SpawnIndex::new(payload).entry_names_at(100, 200, 7);
         assert_eq!(names, vec!["Dragon".to_string()]);
     }
 
@@ -234,10 +699,33 @@ mod tests {
                 entries: vec![("TooFar".to_string(), 0, 0)],
             },
         ];
-        let names = compute_spawn_entry_names(&payload, 103, 203, 7);
+        let names = SpawnIndex::new(payload).entry_names_at(103, 203, 7);
         assert!(names.is_empty());
     }
 
+    #[test]
+    fn spawn_index_reuses_across_queries() {
+        let index = SpawnIndex::new(vec![
+            SpawnAreaPayload {
+                x: 0,
+                y: 0,
+                z: 7,
+                radius: 3,
+                entries: vec![("Rat".to_string(), 1, 1)],
+            },
+            SpawnAreaPayload {
+                x: 50,
+                y: 50,
+                z: 7,
+                radius: 3,
+                entries: vec![("Bug".to_string(), 0, 0)],
+            },
+        ]);
+        assert_eq!(index.entry_names_at(1, 1, 7), vec!["Rat".to_string()]);
+        assert_eq!(index.entry_names_at(50, 50, 7), vec!["Bug".to_string()]);
+        assert!(index.entry_names_at(1, 1, 6).is_empty());
+    }
+
     #[test]
     fn fnv1a_empty() {
         assert_eq!(compute_fnv1a_64(b""), FNV_OFFSET_BASIS_64);
@@ -250,6 +738,76 @@ mod tests {
         assert_eq!(hash, 0xDCB2_7518_FED9_D577);
     }
 
+    #[test]
+    fn blake3_known_value() {
+        // Known BLAKE3 digest of the empty input.
+        let hash = compute_blake3(b"");
+        assert_eq!(
+            hash,
+            [
+                0xaf, 0x13, 0x49, 0xb9, 0xf5, 0xf9, 0xa1, 0xa6, 0xa0, 0x40, 0x4d, 0xea, 0x36, 0xdc,
+                0xc9, 0x49, 0x9b, 0xcb, 0x25, 0xc9, 0xad, 0xc1, 0x12, 0xb7, 0xcc, 0x9a, 0x93, 0xca,
+                0xe4, 0x1f, 0x32, 0x62
+            ]
+        );
+    }
+
+    #[test]
+    fn sprite_hash_256_folds_dimensions() {
+        // Same pixels but different dimensions must not collide.
+        let a = sprite_hash_256(&[1, 2, 3, 4], 2, 2);
+        let b = sprite_hash_256(&[1, 2, 3, 4], 4, 1);
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn chunking_covers_input_without_gaps() {
+        let data: Vec<u8> = (0..4096u32).map(|i| (i * 31) as u8).collect();
+        let chunks = chunk_and_hash(&data, 256, 64, 1024);
+        assert!(!chunks.is_empty());
+        let mut expected = 0u64;
+        for (offset, len, _) in &chunks {
+            assert_eq!(*offset, expected);
+            expected += *len as u64;
+        }
+        assert_eq!(expected, data.len() as u64);
+    }
+
+    #[test]
+    fn dedup_ratio_detects_repeats() {
+        // A buffer of two identical halves repeats its chunks, so some bytes
+        // must be deduplicated.
+        let half: Vec<u8> = (0..8192u32).map(|i| (i * 131 + 7) as u8).collect();
+        let mut data = half.clone();
+        data.extend_from_slice(&half);
+        let stats = dedup_stats(&data, 512, 128, 2048);
+        assert_eq!(stats.total_bytes, data.len() as u64);
+        assert!(stats.unique_bytes < stats.total_bytes);
+        assert!(stats.dedup_ratio() > 0.0);
+    }
+
+    #[test]
+    fn minhash_identical_sprites_match() {
+        let pixels: Vec<u8> = (0..256u32).map(|i| (i * 7) as u8).collect();
+        let a = sprite_minhash(&pixels, 16, 16, 32);
+        let b = sprite_minhash(&pixels, 16, 16, 32);
+        assert_eq!(a, b);
+        assert!((minhash_jaccard(&a, &b) - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn minhash_near_duplicate_is_similar() {
+        let mut pixels: Vec<u8> = (0..1024u32).map(|i| (i * 13 + 5) as u8).collect();
+        let old = sprite_minhash(&pixels, 32, 32, 64);
+        pixels[0] = pixels[0].wrapping_add(1); // one-pixel tweak
+        let new = sprite_minhash(&pixels, 32, 32, 64);
+        let sim = minhash_jaccard(&old, &new);
+        assert!(sim > 0.8, "near-duplicate similarity too low: {sim}");
+
+        let pairs = match_sprites(vec![old], vec![new], 0.8);
+        assert_eq!(pairs, vec![(0, 0)]);
+    }
+
     #[test]
     fn minimap_buffer_basic() {
         // 2x2 tile grid, tile_size=1, all red
@@ -288,10 +846,29 @@ mod tests {
     fn png_idat_basic() {
         // 2x1 image, RGB
         let data: Vec<u8> = vec![255, 0, 0, 0, 255, 0]; // red, green
-        let compressed = assemble_png_idat(&data, 2, 1);
+        let compressed = assemble_png_idat(&data, 2, 1, None);
         // Should be valid zlib data
         let decompressed = miniz_oxide::inflate::decompress_to_vec_zlib(&compressed).unwrap();
         // Should be: filter_byte(0) + row data
         assert_eq!(decompressed, vec![0, 255, 0, 0, 0, 255, 0]);
     }
+
+    #[test]
+    fn paeth_matches_spec() {
+        // Classic PNG spec example: a=left, b=above, c=upper-left.
+        assert_eq!(paeth_predictor(10, 20, 15), 15);
+        // Ties favour a: p = 1, |p-a| = |p-c| = 1, so a (0) wins over c (2).
+        assert_eq!(paeth_predictor(0, 3, 2), 0);
+    }
+
+    #[test]
+    fn png_idat_adaptive_picks_sub_for_gradient_row() {
+        // A horizontal gradient: Sub (filter 1) yields a constant delta and
+        // wins over None on the adaptive path.
+        let data: Vec<u8> = vec![0, 0, 0, 3, 3, 3, 6, 6, 6];
+        let compressed = assemble_png_idat(&data, 3, 1, Some("adaptive"));
+        let decompressed = miniz_oxide::inflate::decompress_to_vec_zlib(&compressed).unwrap();
+        assert_eq!(decompressed[0], 1); // Sub
+        assert_eq!(&decompressed[1..], &[0, 0, 0, 3, 3, 3, 3, 3, 3]);
+    }
 }